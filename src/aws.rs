@@ -0,0 +1,150 @@
+//! A [`FrameDecoder`] for the length-prefixed binary `vnd.amazon.eventstream` framing used by
+//! several AWS services.
+//!
+//! Each message starts with a 12-byte prelude (`total_length: u32`, `headers_length: u32`, then
+//! a CRC32 of those first 8 bytes), followed by `headers_length` bytes of headers, a payload,
+//! and a trailing CRC32 of the whole message. The `:event-type` header becomes [`Event::event`]
+//! and the payload becomes [`Event::data`].
+
+#[cfg(not(feature = "std"))]
+use alloc::{collections::VecDeque, string::String, vec::Vec};
+#[cfg(feature = "std")]
+use std::collections::VecDeque;
+
+use crate::decoder::FrameDecoder;
+use crate::{Event, ParseError};
+
+const PRELUDE_LEN: usize = 8;
+const PRELUDE_CRC_LEN: usize = 4;
+const MESSAGE_CRC_LEN: usize = 4;
+const EVENT_TYPE_HEADER: &str = ":event-type";
+
+/// Decodes the binary `vnd.amazon.eventstream` framing.
+#[derive(Debug, Default)]
+pub struct AwsEventStreamDecoder {
+    _private: (),
+}
+
+impl FrameDecoder for AwsEventStreamDecoder {
+    fn decode(&mut self, buffer: &mut Vec<u8>, results: &mut VecDeque<Result<Event, ParseError>>) {
+        loop {
+            if buffer.len() < PRELUDE_LEN + PRELUDE_CRC_LEN {
+                return;
+            }
+
+            let prelude_crc = u32::from_be_bytes(read_array(buffer, 8));
+            if crc32fast::hash(&buffer[..PRELUDE_LEN]) != prelude_crc {
+                results.push_back(Err(ParseError::InvalidChecksum));
+                // There's no well-defined way to resynchronize after a corrupt prelude; drop
+                // everything buffered so far rather than spinning on the same bytes forever.
+                buffer.clear();
+                return;
+            }
+
+            let total_length = u32::from_be_bytes(read_array(buffer, 0)) as usize;
+            let headers_length = u32::from_be_bytes(read_array(buffer, 4)) as usize;
+            if total_length < PRELUDE_LEN + PRELUDE_CRC_LEN + MESSAGE_CRC_LEN {
+                results.push_back(Err(ParseError::TruncatedFrame));
+                buffer.clear();
+                return;
+            }
+
+            if buffer.len() < total_length {
+                // Wait for the rest of the message to arrive.
+                return;
+            }
+
+            let message: Vec<u8> = buffer.drain(..total_length).collect();
+            results.push_back(decode_message(&message, headers_length));
+        }
+    }
+
+    fn flush(&mut self, buffer: &mut Vec<u8>, results: &mut VecDeque<Result<Event, ParseError>>) {
+        if !buffer.is_empty() {
+            buffer.clear();
+            results.push_back(Err(ParseError::TruncatedFrame));
+        }
+    }
+}
+
+fn read_array<const N: usize>(buffer: &[u8], offset: usize) -> [u8; N] {
+    buffer[offset..offset + N].try_into().unwrap()
+}
+
+fn decode_message(message: &[u8], headers_length: usize) -> Result<Event, ParseError> {
+    let message_crc = u32::from_be_bytes(read_array(message, message.len() - MESSAGE_CRC_LEN));
+    if crc32fast::hash(&message[..message.len() - MESSAGE_CRC_LEN]) != message_crc {
+        return Err(ParseError::InvalidChecksum);
+    }
+
+    let headers_start = PRELUDE_LEN + PRELUDE_CRC_LEN;
+    let headers_end = headers_start + headers_length;
+    let headers = message
+        .get(headers_start..headers_end)
+        .ok_or(ParseError::TruncatedFrame)?;
+    let payload = message
+        .get(headers_end..message.len() - MESSAGE_CRC_LEN)
+        .ok_or(ParseError::TruncatedFrame)?;
+
+    let mut event = Event {
+        data: payload.to_vec(),
+        ..Event::default()
+    };
+
+    let mut rest = headers;
+    while !rest.is_empty() {
+        let (name, value, remainder) = decode_header(rest)?;
+        rest = remainder;
+        if name == EVENT_TYPE_HEADER {
+            event.event = value;
+        }
+    }
+
+    Ok(event)
+}
+
+/// Decode a single `name_len: u8, name, value_type: u8, value` header, returning the header's
+/// name, its value if it was a string, and the remaining unparsed headers.
+fn decode_header(header: &[u8]) -> Result<(String, Option<String>, &[u8]), ParseError> {
+    let name_len = *header.first().ok_or(ParseError::TruncatedFrame)? as usize;
+    let header = header.get(1..).ok_or(ParseError::TruncatedFrame)?;
+    let name_bytes = header.get(..name_len).ok_or(ParseError::TruncatedFrame)?;
+    let name = String::from_utf8_lossy(name_bytes).into_owned();
+    let header = &header[name_len..];
+
+    let value_type = *header.first().ok_or(ParseError::TruncatedFrame)?;
+    let header = header.get(1..).ok_or(ParseError::TruncatedFrame)?;
+
+    // Fixed-size value types that aren't needed to reach `:event-type`, but still have to be
+    // skipped correctly so that parsing can continue to the next header.
+    let fixed_len = match value_type {
+        0 | 1 => Some(0),  // bool true / false
+        2 => Some(1),      // byte
+        3 => Some(2),      // short
+        4 => Some(4),      // integer
+        5 | 8 => Some(8),  // long / timestamp
+        9 => Some(16),     // uuid
+        _ => None,
+    };
+
+    if let Some(len) = fixed_len {
+        let header = header.get(len..).ok_or(ParseError::TruncatedFrame)?;
+        return Ok((name, None, header));
+    }
+
+    match value_type {
+        6 | 7 => {
+            let len_bytes = header.get(..2).ok_or(ParseError::TruncatedFrame)?;
+            let len = u16::from_be_bytes(len_bytes.try_into().unwrap()) as usize;
+            let value_bytes = header.get(2..2 + len).ok_or(ParseError::TruncatedFrame)?;
+            let header = &header[2 + len..];
+            let value = if value_type == 7 {
+                Some(String::from_utf8_lossy(value_bytes).into_owned())
+            } else {
+                None
+            };
+            Ok((name, value, header))
+        }
+        _ => Err(ParseError::InvalidField(name.into_bytes())),
+    }
+}
@@ -0,0 +1,29 @@
+//! The [`FrameDecoder`] trait, which lets [`EventStreamTransformer`](crate::EventStreamTransformer)
+//! be reused for wire formats other than the default WHATWG SSE text grammar (see [`crate::sse`]
+//! and [`crate::aws`]).
+
+#[cfg(not(feature = "std"))]
+use alloc::{collections::VecDeque, vec::Vec};
+#[cfg(feature = "std")]
+use std::collections::VecDeque;
+
+use crate::{Event, ParseError};
+
+/// Decodes frames out of an accumulated byte buffer into zero or more [`Event`]s.
+///
+/// `EventStreamTransformer` appends every newly received chunk onto `buffer` and then calls
+/// [`decode`](FrameDecoder::decode); implementations should pull as many complete frames as are
+/// currently available out of the front of `buffer`, pushing a result for each onto the back of
+/// `results`, and either leave any incomplete trailing frame in `buffer` for the next call or
+/// drain it and carry the partial frame in their own state (as [`crate::sse::SseDecoder`] does).
+pub trait FrameDecoder {
+    /// Decode as many complete frames as `buffer` currently holds.
+    fn decode(&mut self, buffer: &mut Vec<u8>, results: &mut VecDeque<Result<Event, ParseError>>);
+
+    /// Called once the underlying byte stream has ended, giving the decoder a chance to flush a
+    /// final frame that its wire format doesn't otherwise terminate. The default implementation
+    /// does nothing.
+    fn flush(&mut self, buffer: &mut Vec<u8>, results: &mut VecDeque<Result<Event, ParseError>>) {
+        let _ = (buffer, results);
+    }
+}
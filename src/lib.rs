@@ -44,6 +44,14 @@ use core::{fmt, mem};
 use futures_core::stream::Stream;
 use futures_core::task::{Context, Poll};
 
+pub mod aws;
+pub mod decoder;
+pub mod reconnect;
+pub mod sse;
+pub mod typed;
+
+use decoder::FrameDecoder;
+
 /// An Event
 #[derive(Default, Debug, Eq, PartialEq)]
 pub struct Event {
@@ -57,22 +65,6 @@ pub struct Event {
     pub retry: Option<Duration>,
 }
 
-#[derive(Debug, Clone, Copy)]
-enum Field {
-    Event,
-    Data,
-    Id,
-    Retry,
-}
-
-#[derive(Debug, Copy, Clone)]
-enum State {
-    ReadingField,
-    ReadingValue,
-    NextLine,
-    Closed,
-}
-
 /// Wrapper for [`ParseError`] and other Transport Errors thrown while collecting the
 /// [`Event`] stream
 #[derive(Debug)]
@@ -83,7 +75,7 @@ pub enum Error<T> {
     Transport(T),
 }
 
-/// Error thrown while parsing an event line
+/// Error thrown while decoding a frame
 #[derive(Debug, Fail)]
 pub enum ParseError {
     /// Field name parsing error. Field must be one of `event`, `data`, `id` or `retry`. Contains
@@ -102,6 +94,17 @@ pub enum ParseError {
     /// No field found on line
     #[fail(display = "empty field")]
     EmptyField,
+    /// A frame's checksum didn't match its contents, as used by e.g. [`aws::AwsEventStreamDecoder`]
+    #[fail(display = "invalid checksum")]
+    InvalidChecksum,
+    /// A frame was incomplete when the stream ended, or its lengths were inconsistent with its
+    /// contents, as used by e.g. [`aws::AwsEventStreamDecoder`]
+    #[fail(display = "truncated frame")]
+    TruncatedFrame,
+    /// An event's `data` couldn't be deserialized into its target type, as used by
+    /// [`typed::TypedEventStream`]
+    #[fail(display = "invalid typed event data: {}", _0)]
+    Deserialize(String),
 }
 
 /// Main entrypoint for creating [`Event`] streams
@@ -110,99 +113,68 @@ pub trait Eventsource: Sized {
     fn eventsource(self) -> EventStreamTransformer<Self>;
 }
 
-impl Field {
-    fn from_bytes(bytes: Vec<u8>) -> Result<Field, ParseError> {
-        let string =
-            String::from_utf8(bytes).map_err(|e| ParseError::InvalidField(e.into_bytes()))?;
-        if string.is_empty() {
-            return Err(ParseError::EmptyField);
-        }
-        Ok(match string.as_ref() {
-            "event" => Field::Event,
-            "data" => Field::Data,
-            "id" => Field::Id,
-            "retry" => Field::Retry,
-            _ => return Err(ParseError::InvalidField(string.into_bytes())),
-        })
-    }
-}
-
 impl Event {
     /// Check if an event is the default empty event
     pub fn is_empty(&self) -> bool {
         self == &Event::default()
     }
 
-    fn set_field(&mut self, field: Option<Field>, mut data: Vec<u8>) -> Result<(), ParseError> {
-        match field {
-            Some(Field::Event) => {
-                let event = String::from_utf8(data)
-                    .map_err(|e| ParseError::InvalidEvent(e.into_bytes()))?;
-                self.event = Some(event);
-            }
-            Some(Field::Data) => {
-                self.data.append(&mut data);
-            }
-            Some(Field::Id) => {
-                self.id = Some(data);
-            }
-            Some(Field::Retry) => {
-                let ms = String::from_utf8(data)
-                    .map_err(|e| ParseError::InvalidRetry(e.into_bytes()))
-                    .and_then(|string| {
-                        string
-                            .parse()
-                            .map_err(|_| ParseError::InvalidRetry(string.into_bytes()))
-                    })?;
-                self.retry = Some(Duration::from_millis(ms));
-            }
-            None => {}
-        };
-        Ok(())
+    /// Take the event out, ready for dispatch, dropping the trailing `\n` that separates
+    /// `data` lines from one another but isn't part of the value itself.
+    pub(crate) fn take(&mut self) -> Event {
+        if self.data.last() == Some(&b'\n') {
+            self.data.pop();
+        }
+        mem::take(self)
     }
 }
 
-/// Provides the [`Stream`] implementation for Events
-pub struct EventStreamTransformer<S> {
+/// Provides the [`Stream`] implementation for Events, decoding frames out of the underlying
+/// byte stream through a pluggable [`FrameDecoder`] (the WHATWG SSE grammar, via [`sse::SseDecoder`],
+/// by default).
+pub struct EventStreamTransformer<S, D = sse::SseDecoder> {
     buffer: Vec<u8>,
-    field: Option<Field>,
-    event: Event,
-    state: State,
+    decoder: D,
+    closed: bool,
     results: VecDeque<Result<Event, ParseError>>,
     stream: S,
 }
 
-struct EventStreamTransformerProjection<'a, S> {
+struct EventStreamTransformerProjection<'a, S, D> {
     buffer: &'a mut Vec<u8>,
-    field: &'a mut Option<Field>,
-    event: &'a mut Event,
-    state: &'a mut State,
+    decoder: &'a mut D,
+    closed: &'a mut bool,
     results: &'a mut VecDeque<Result<Event, ParseError>>,
     stream: &'a mut S,
 }
 
-impl<S> EventStreamTransformer<S> {
-    /// Wrap a stream of bytes
+impl<S, D: Default> EventStreamTransformer<S, D> {
+    /// Wrap a stream of bytes, decoding it with `D`'s default configuration
     pub fn wrap(stream: S) -> Self {
+        Self::with_decoder(stream, D::default())
+    }
+}
+
+impl<S, D> EventStreamTransformer<S, D> {
+    /// Wrap a stream of bytes, decoding it with the given [`FrameDecoder`]
+    pub fn with_decoder(stream: S, decoder: D) -> Self {
         Self {
             buffer: Vec::default(),
-            field: None,
-            event: Event::default(),
-            state: State::ReadingField,
+            decoder,
+            closed: false,
             results: Default::default(),
             stream,
         }
     }
 
     #[inline]
-    fn projection<'a>(self: Pin<&'a mut Self>) -> EventStreamTransformerProjection<'a, S> {
+    fn projection<'a>(self: Pin<&'a mut Self>) -> EventStreamTransformerProjection<'a, S, D> {
         unsafe {
             let inner = self.get_unchecked_mut();
             EventStreamTransformerProjection {
                 buffer: &mut inner.buffer,
-                field: &mut inner.field,
-                event: &mut inner.event,
-                state: &mut inner.state,
+                decoder: &mut inner.decoder,
+                closed: &mut inner.closed,
                 results: &mut inner.results,
                 stream: &mut inner.stream,
             }
@@ -210,20 +182,21 @@ impl<S> EventStreamTransformer<S> {
     }
 }
 
-impl<S, B, E> Stream for EventStreamTransformer<S>
+impl<S, B, E, D> Stream for EventStreamTransformer<S, D>
 where
     S: Stream<Item = Result<B, E>>,
     B: AsRef<[u8]>,
+    D: FrameDecoder,
 {
     type Item = Result<Event, Error<E>>;
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
         let this = self.projection();
 
-        if let Some(res) = this.results.pop_back() {
+        if let Some(res) = this.results.pop_front() {
             return Poll::Ready(Some(res.map_err(Error::Parse)));
         }
-        if matches!(this.state, State::Closed) {
+        if *this.closed {
             return Poll::Ready(None);
         }
 
@@ -231,17 +204,15 @@ where
             let stream = unsafe { Pin::new_unchecked(&mut *this.stream) };
             let chunk = match stream.poll_next(cx) {
                 Poll::Pending => {
-                    if let Some(res) = this.results.pop_back() {
+                    if let Some(res) = this.results.pop_front() {
                         return Poll::Ready(Some(res.map_err(Error::Parse)));
                     }
                     return Poll::Pending;
                 }
                 Poll::Ready(None) => {
-                    if !this.event.is_empty() {
-                        this.results.push_front(Ok(mem::take(this.event)));
-                    }
-                    *this.state = State::Closed;
-                    if let Some(res) = this.results.pop_back() {
+                    this.decoder.flush(this.buffer, this.results);
+                    *this.closed = true;
+                    if let Some(res) = this.results.pop_front() {
                         return Poll::Ready(Some(res.map_err(Error::Parse)));
                     }
                     return Poll::Ready(None);
@@ -252,64 +223,11 @@ where
                 Poll::Ready(Some(Ok(chunk))) => chunk,
             };
 
-            for byte in chunk.as_ref() {
-                match byte {
-                    b'\n' => match this.state {
-                        State::ReadingField => {
-                            if !this.buffer.is_empty() {
-                                this.results.push_front(Err(ParseError::UnexpectedEndOfLine(
-                                    mem::take(this.buffer),
-                                )));
-                                this.buffer.clear();
-                            }
-                            *this.field = None;
-                            *this.state = State::NextLine;
-                        }
-                        State::ReadingValue => {
-                            if let Err(e) = this
-                                .event
-                                .set_field(mem::take(this.field), mem::take(this.buffer))
-                            {
-                                this.results.push_front(Err(e));
-                            }
-                            *this.state = State::NextLine;
-                        }
-                        State::NextLine => {
-                            this.results.push_front(Ok(mem::take(this.event)));
-                            this.buffer.clear();
-                            *this.field = None;
-                            *this.state = State::ReadingField;
-                        }
-                        State::Closed => unreachable!(),
-                    },
-                    b':' => match this.state {
-                        State::ReadingField => {
-                            match Field::from_bytes(mem::take(this.buffer)) {
-                                Ok(next_field) => {
-                                    *this.field = Some(next_field);
-                                }
-                                Err(e) => {
-                                    this.results.push_front(Err(e));
-                                }
-                            }
-                            *this.state = State::ReadingValue;
-                        }
-                        State::ReadingValue => {
-                            this.buffer.push(*byte);
-                        }
-                        State::NextLine => {
-                            this.results.push_front(Err(ParseError::EmptyField));
-                            *this.state = State::ReadingValue;
-                        }
-                        State::Closed => unreachable!(),
-                    },
-                    byte => {
-                        if matches!(this.state, State::NextLine) {
-                            *this.state = State::ReadingField;
-                        }
-                        this.buffer.push(*byte);
-                    }
-                }
+            this.buffer.extend_from_slice(chunk.as_ref());
+            this.decoder.decode(this.buffer, this.results);
+
+            if let Some(res) = this.results.pop_front() {
+                return Poll::Ready(Some(res.map_err(Error::Parse)));
             }
         }
     }
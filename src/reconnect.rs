@@ -0,0 +1,333 @@
+//! Auto-reconnecting wrapper around [`EventStreamTransformer`], built from a user-supplied
+//! "connect" closure. Borrows the reconnection model used by streaming
+//! Mastodon/ActivityPub clients: when the underlying byte stream ends or errors, a new one is
+//! requested instead of terminating the [`Stream`], with the last seen [`Event::id`] handed
+//! back so the caller can send it as `Last-Event-ID` on the new request.
+
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, vec::Vec};
+
+use core::fmt;
+use core::future::Future;
+use core::pin::Pin;
+use core::time::Duration;
+use futures_core::stream::Stream;
+use futures_core::task::{Context, Poll};
+
+use crate::{Error, Event, EventStreamTransformer, ParseError};
+
+/// The default delay used before the first `retry` value is seen, matching the 3 second
+/// default reconnection time suggested by the SSE spec.
+const DEFAULT_RETRY_DELAY: Duration = Duration::from_secs(3);
+
+/// Caps how many times `poll_next` can cycle Connecting→Sleeping→Connecting (or
+/// Streaming→Sleeping→Connecting) synchronously within a single call, in case `connect` and
+/// `sleep` both resolve immediately (e.g. a server-sent `retry: 0`). Past this, `poll_next`
+/// yields `Pending` and re-wakes itself rather than busy-looping the executor forever.
+const MAX_SYNCHRONOUS_TRANSITIONS: usize = 16;
+
+/// Observable connection state of a [`ReconnectingEventSource`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// A connect attempt is in flight
+    Connecting,
+    /// Events are being read from the current connection
+    Connected,
+    /// Waiting out the retry delay before the next connect attempt
+    Reconnecting,
+    /// The retry policy was exhausted; no further attempts will be made
+    Closed,
+}
+
+/// Controls how many times a [`ReconnectingEventSource`] will attempt to reconnect after a
+/// failed connect attempt or a dropped connection before giving up
+#[derive(Debug, Clone, Copy)]
+pub enum RetryPolicy {
+    /// Keep retrying forever
+    Forever,
+    /// Give up after this many consecutive failures, where a failed connect attempt and a
+    /// connection that errors or closes each count as one; a successful connect resets the count
+    MaxAttempts(usize),
+}
+
+impl RetryPolicy {
+    fn allows(&self, attempts: usize) -> bool {
+        match self {
+            RetryPolicy::Forever => true,
+            RetryPolicy::MaxAttempts(max) => attempts < *max,
+        }
+    }
+}
+
+/// Error yielded by a [`ReconnectingEventSource`]
+#[derive(Debug)]
+pub enum ReconnectError<E> {
+    /// A malformed line was received; parsing resumes on the next line
+    Parse(ParseError),
+    /// The [`RetryPolicy`] was exhausted after repeated failed connect attempts or dropped
+    /// connections
+    GaveUp {
+        /// The transport error from the last failed attempt, if any — a connection that was
+        /// simply closed (rather than erroring) before the policy was exhausted has none
+        last_error: Option<E>,
+        /// How many consecutive failed attempts were made before giving up
+        attempts: usize,
+    },
+}
+
+impl<T> fmt::Display for ReconnectError<T>
+where
+    T: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(err) => f.write_fmt(format_args!("parse error: {}", err)),
+            Self::GaveUp {
+                last_error: Some(err),
+                attempts,
+            } => f.write_fmt(format_args!(
+                "gave up reconnecting after {} attempt(s): {}",
+                attempts, err
+            )),
+            Self::GaveUp {
+                last_error: None,
+                attempts,
+            } => f.write_fmt(format_args!(
+                "gave up reconnecting after {} attempt(s): connection closed",
+                attempts
+            )),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> std::error::Error for ReconnectError<T> where T: fmt::Display + fmt::Debug + Send + Sync {}
+
+enum Phase<F, St, D> {
+    // `F`/`D` are arbitrary, possibly self-referential futures from caller-supplied closures,
+    // so they're boxed and pinned up front rather than pinned in place later: that way whether
+    // `Phase`, and so `ReconnectingEventSource`, is `Unpin` (which `Stream::next` and friends
+    // require of `Self`) doesn't depend on `F`/`D`, only on `St`, `C` and `W`.
+    Connecting(Pin<Box<F>>),
+    Streaming(EventStreamTransformer<St>),
+    Sleeping(Pin<Box<D>>),
+    Closed,
+}
+
+/// Wraps a user-supplied "connect" closure and "sleep" closure into a self-reconnecting
+/// [`Stream`] of [`Event`]s. See the [module docs](self) for the reconnection model.
+pub struct ReconnectingEventSource<C, F, St, B, E, W, D>
+where
+    C: FnMut(Option<&[u8]>) -> F,
+    F: Future<Output = Result<St, E>>,
+    St: Stream<Item = Result<B, E>>,
+    B: AsRef<[u8]>,
+    W: FnMut(Duration) -> D,
+    D: Future<Output = ()>,
+{
+    connect: C,
+    sleep: W,
+    phase: Phase<F, St, D>,
+    last_event_id: Option<Vec<u8>>,
+    retry_delay: Duration,
+    retry_policy: RetryPolicy,
+    attempts: usize,
+    state: ConnectionState,
+}
+
+struct ReconnectingEventSourceProjection<'a, C, F, St, B, E, W, D>
+where
+    C: FnMut(Option<&[u8]>) -> F,
+    F: Future<Output = Result<St, E>>,
+    St: Stream<Item = Result<B, E>>,
+    B: AsRef<[u8]>,
+    W: FnMut(Duration) -> D,
+    D: Future<Output = ()>,
+{
+    connect: &'a mut C,
+    sleep: &'a mut W,
+    phase: &'a mut Phase<F, St, D>,
+    last_event_id: &'a mut Option<Vec<u8>>,
+    retry_delay: &'a mut Duration,
+    retry_policy: &'a RetryPolicy,
+    attempts: &'a mut usize,
+    state: &'a mut ConnectionState,
+}
+
+impl<C, F, St, B, E, W, D> ReconnectingEventSource<C, F, St, B, E, W, D>
+where
+    C: FnMut(Option<&[u8]>) -> F,
+    F: Future<Output = Result<St, E>>,
+    St: Stream<Item = Result<B, E>>,
+    B: AsRef<[u8]>,
+    W: FnMut(Duration) -> D,
+    D: Future<Output = ()>,
+{
+    /// Create a new source, immediately starting a first connect attempt. `connect` is handed
+    /// the last non-empty [`Event::id`] seen so far (`None` on the first attempt), and `sleep`
+    /// is used to wait out the reconnect delay between attempts.
+    pub fn new(mut connect: C, sleep: W) -> Self {
+        let phase = Phase::Connecting(Box::pin(connect(None)));
+        Self {
+            connect,
+            sleep,
+            phase,
+            last_event_id: None,
+            retry_delay: DEFAULT_RETRY_DELAY,
+            retry_policy: RetryPolicy::Forever,
+            attempts: 0,
+            state: ConnectionState::Connecting,
+        }
+    }
+
+    /// Give up reconnecting after `policy` is exhausted instead of retrying forever
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Override the delay used before any server-sent `retry` field has been seen
+    pub fn with_default_retry_delay(mut self, delay: Duration) -> Self {
+        self.retry_delay = delay;
+        self
+    }
+
+    /// The current connection state
+    pub fn state(&self) -> ConnectionState {
+        self.state
+    }
+
+    #[inline]
+    fn projection(
+        self: Pin<&mut Self>,
+    ) -> ReconnectingEventSourceProjection<'_, C, F, St, B, E, W, D> {
+        unsafe {
+            let inner = self.get_unchecked_mut();
+            ReconnectingEventSourceProjection {
+                connect: &mut inner.connect,
+                sleep: &mut inner.sleep,
+                phase: &mut inner.phase,
+                last_event_id: &mut inner.last_event_id,
+                retry_delay: &mut inner.retry_delay,
+                retry_policy: &inner.retry_policy,
+                attempts: &mut inner.attempts,
+                state: &mut inner.state,
+            }
+        }
+    }
+}
+
+impl<C, F, St, B, E, W, D> Stream for ReconnectingEventSource<C, F, St, B, E, W, D>
+where
+    C: FnMut(Option<&[u8]>) -> F,
+    F: Future<Output = Result<St, E>>,
+    St: Stream<Item = Result<B, E>>,
+    B: AsRef<[u8]>,
+    W: FnMut(Duration) -> D,
+    D: Future<Output = ()>,
+{
+    type Item = Result<Event, ReconnectError<E>>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        for _ in 0..MAX_SYNCHRONOUS_TRANSITIONS {
+            let this = self.as_mut().projection();
+
+            if matches!(this.phase, Phase::Closed) {
+                return Poll::Ready(None);
+            }
+
+            match this.phase {
+                Phase::Connecting(fut) => {
+                    match fut.as_mut().poll(cx) {
+                        Poll::Pending => return Poll::Pending,
+                        Poll::Ready(Ok(stream)) => {
+                            *this.attempts = 0;
+                            *this.state = ConnectionState::Connected;
+                            *this.phase = Phase::Streaming(EventStreamTransformer::wrap(stream));
+                        }
+                        Poll::Ready(Err(e)) => {
+                            *this.attempts += 1;
+                            if !this.retry_policy.allows(*this.attempts) {
+                                *this.state = ConnectionState::Closed;
+                                *this.phase = Phase::Closed;
+                                return Poll::Ready(Some(Err(ReconnectError::GaveUp {
+                                    last_error: Some(e),
+                                    attempts: *this.attempts,
+                                })));
+                            }
+                            *this.state = ConnectionState::Reconnecting;
+                            *this.phase =
+                                Phase::Sleeping(Box::pin((this.sleep)(*this.retry_delay)));
+                        }
+                    }
+                }
+                Phase::Sleeping(fut) => {
+                    match fut.as_mut().poll(cx) {
+                        Poll::Pending => return Poll::Pending,
+                        Poll::Ready(()) => {
+                            *this.state = ConnectionState::Connecting;
+                            let id = this.last_event_id.as_deref();
+                            *this.phase = Phase::Connecting(Box::pin((this.connect)(id)));
+                        }
+                    }
+                }
+                Phase::Streaming(transformer) => {
+                    let transformer = unsafe { Pin::new_unchecked(transformer) };
+                    match transformer.poll_next(cx) {
+                        Poll::Pending => return Poll::Pending,
+                        Poll::Ready(Some(Ok(event))) => {
+                            if let Some(id) = &event.id {
+                                if !id.is_empty() {
+                                    *this.last_event_id = Some(id.clone());
+                                }
+                            }
+                            if let Some(retry) = event.retry {
+                                *this.retry_delay = retry;
+                            }
+                            return Poll::Ready(Some(Ok(event)));
+                        }
+                        Poll::Ready(Some(Err(Error::Parse(e)))) => {
+                            return Poll::Ready(Some(Err(ReconnectError::Parse(e))));
+                        }
+                        Poll::Ready(Some(Err(Error::Transport(e)))) => {
+                            *this.attempts += 1;
+                            if !this.retry_policy.allows(*this.attempts) {
+                                *this.state = ConnectionState::Closed;
+                                *this.phase = Phase::Closed;
+                                return Poll::Ready(Some(Err(ReconnectError::GaveUp {
+                                    last_error: Some(e),
+                                    attempts: *this.attempts,
+                                })));
+                            }
+                            *this.state = ConnectionState::Reconnecting;
+                            *this.phase =
+                                Phase::Sleeping(Box::pin((this.sleep)(*this.retry_delay)));
+                        }
+                        Poll::Ready(None) => {
+                            *this.attempts += 1;
+                            if !this.retry_policy.allows(*this.attempts) {
+                                *this.state = ConnectionState::Closed;
+                                *this.phase = Phase::Closed;
+                                return Poll::Ready(Some(Err(ReconnectError::GaveUp {
+                                    last_error: None,
+                                    attempts: *this.attempts,
+                                })));
+                            }
+                            *this.state = ConnectionState::Reconnecting;
+                            *this.phase =
+                                Phase::Sleeping(Box::pin((this.sleep)(*this.retry_delay)));
+                        }
+                    }
+                }
+                Phase::Closed => unreachable!(),
+            }
+        }
+
+        // Cycled through Connecting/Sleeping/Streaming `MAX_SYNCHRONOUS_TRANSITIONS` times
+        // without reaching a point that needs to wait on anything external; yield to the
+        // executor instead of spinning forever, but ask to be polled again immediately so
+        // progress continues.
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
@@ -0,0 +1,189 @@
+//! The default [`FrameDecoder`], implementing the WHATWG text grammar for Server-Sent Events:
+//! CR, LF and CRLF line endings, `:`-prefixed comment lines, and a single optional leading space
+//! after a field's `:`.
+
+#[cfg(not(feature = "std"))]
+use alloc::{collections::VecDeque, string::String, vec::Vec};
+#[cfg(feature = "std")]
+use std::collections::VecDeque;
+
+use core::mem;
+use core::time::Duration;
+
+use crate::decoder::FrameDecoder;
+use crate::{Event, ParseError};
+
+#[derive(Debug, Clone, Copy)]
+enum Field {
+    Event,
+    Data,
+    Id,
+    Retry,
+}
+
+impl Field {
+    fn from_bytes(bytes: Vec<u8>) -> Result<Field, ParseError> {
+        let string =
+            String::from_utf8(bytes).map_err(|e| ParseError::InvalidField(e.into_bytes()))?;
+        if string.is_empty() {
+            return Err(ParseError::EmptyField);
+        }
+        Ok(match string.as_ref() {
+            "event" => Field::Event,
+            "data" => Field::Data,
+            "id" => Field::Id,
+            "retry" => Field::Retry,
+            _ => return Err(ParseError::InvalidField(string.into_bytes())),
+        })
+    }
+}
+
+impl Event {
+    fn set_field(&mut self, field: Option<Field>, mut data: Vec<u8>) -> Result<(), ParseError> {
+        match field {
+            Some(Field::Event) => {
+                let event = String::from_utf8(data)
+                    .map_err(|e| ParseError::InvalidEvent(e.into_bytes()))?;
+                self.event = Some(event);
+            }
+            Some(Field::Data) => {
+                self.data.append(&mut data);
+                self.data.push(b'\n');
+            }
+            Some(Field::Id) => {
+                self.id = Some(data);
+            }
+            Some(Field::Retry) => {
+                let ms = String::from_utf8(data)
+                    .map_err(|e| ParseError::InvalidRetry(e.into_bytes()))
+                    .and_then(|string| {
+                        string
+                            .parse()
+                            .map_err(|_| ParseError::InvalidRetry(string.into_bytes()))
+                    })?;
+                self.retry = Some(Duration::from_millis(ms));
+            }
+            None => {}
+        };
+        Ok(())
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+enum State {
+    #[default]
+    ReadingField,
+    ReadingValue,
+    /// Inside a comment line (one starting with `:`); its content is discarded until the next
+    /// line terminator.
+    Comment,
+    NextLine,
+}
+
+/// Decodes the text Server-Sent Events grammar. This is the default decoder used by
+/// [`EventStreamTransformer::wrap`](crate::EventStreamTransformer::wrap).
+#[derive(Debug, Default)]
+pub struct SseDecoder {
+    value: Vec<u8>,
+    field: Option<Field>,
+    event: Event,
+    state: State,
+    /// Set right after a field-separating `:` is read; causes the single following space, if
+    /// any, to be dropped from the value.
+    skip_leading_space: bool,
+    /// Set after reading a bare `\r`; causes an immediately following `\n` to be swallowed
+    /// instead of being treated as a second line terminator.
+    pending_cr: bool,
+}
+
+impl FrameDecoder for SseDecoder {
+    fn decode(&mut self, buffer: &mut Vec<u8>, results: &mut VecDeque<Result<Event, ParseError>>) {
+        for byte in buffer.drain(..) {
+            if mem::take(&mut self.pending_cr) && byte == b'\n' {
+                continue;
+            }
+
+            if byte == b'\r' || byte == b'\n' {
+                if byte == b'\r' {
+                    self.pending_cr = true;
+                }
+                match self.state {
+                    State::ReadingField => {
+                        if !self.value.is_empty() {
+                            results.push_back(Err(ParseError::UnexpectedEndOfLine(mem::take(
+                                &mut self.value,
+                            ))));
+                            self.value.clear();
+                        }
+                        self.field = None;
+                        self.state = State::NextLine;
+                    }
+                    State::ReadingValue => {
+                        if let Err(e) = self
+                            .event
+                            .set_field(mem::take(&mut self.field), mem::take(&mut self.value))
+                        {
+                            results.push_back(Err(e));
+                        }
+                        self.state = State::NextLine;
+                    }
+                    State::Comment => {
+                        self.value.clear();
+                        self.state = State::NextLine;
+                    }
+                    State::NextLine => {
+                        results.push_back(Ok(self.event.take()));
+                        self.value.clear();
+                        self.field = None;
+                        self.state = State::ReadingField;
+                    }
+                }
+                continue;
+            }
+
+            match self.state {
+                State::ReadingField => {
+                    if byte == b':' {
+                        if self.value.is_empty() {
+                            // A line starting with `:` is a comment; ignore it entirely.
+                            self.state = State::Comment;
+                        } else {
+                            match Field::from_bytes(mem::take(&mut self.value)) {
+                                Ok(next_field) => self.field = Some(next_field),
+                                Err(e) => results.push_back(Err(e)),
+                            }
+                            self.state = State::ReadingValue;
+                            self.skip_leading_space = true;
+                        }
+                    } else {
+                        self.value.push(byte);
+                    }
+                }
+                State::ReadingValue => {
+                    if mem::take(&mut self.skip_leading_space) && byte == b' ' {
+                        // Drop exactly one leading space after the field's `:`.
+                    } else {
+                        self.value.push(byte);
+                    }
+                }
+                State::Comment => {
+                    // Comment content is discarded.
+                }
+                State::NextLine => {
+                    if byte == b':' {
+                        self.state = State::Comment;
+                    } else {
+                        self.state = State::ReadingField;
+                        self.value.push(byte);
+                    }
+                }
+            }
+        }
+    }
+
+    fn flush(&mut self, _buffer: &mut Vec<u8>, results: &mut VecDeque<Result<Event, ParseError>>) {
+        if !self.event.is_empty() {
+            results.push_back(Ok(self.event.take()));
+        }
+    }
+}
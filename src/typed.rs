@@ -0,0 +1,100 @@
+//! Typed event deserialization keyed on the event name, via [`TypedEvents::typed_events`].
+
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+
+use core::marker::PhantomData;
+use core::pin::Pin;
+use futures_core::stream::Stream;
+use futures_core::task::{Context, Poll};
+
+use crate::{Error, Event, ParseError};
+
+/// Error returned by [`DeserializeEvent::deserialize_event`]
+#[derive(Debug)]
+pub enum DeserializeError {
+    /// `event_type` didn't match any of `Self`'s variants; the event is skipped rather than
+    /// surfaced as an error by [`TypedEventStream`]
+    UnknownEventType,
+    /// `data` couldn't be deserialized into `Self`. Contains a message describing the failure
+    Invalid(String),
+}
+
+/// Deserializes an [`Event`]'s payload into a strongly-typed value, dispatching on the event's
+/// `event` name (`""` if it had none). Implement this directly to dispatch into an enum's
+/// variants, returning [`DeserializeError::UnknownEventType`] for names that don't match any of
+/// them; see also the blanket impl over [`serde::de::DeserializeOwned`] gated behind the `json`
+/// feature, which ignores `event_type` and parses `data` as JSON.
+pub trait DeserializeEvent: Sized {
+    /// Deserialize `data`, given the event's `event` name
+    fn deserialize_event(event_type: &str, data: &[u8]) -> Result<Self, DeserializeError>;
+}
+
+#[cfg(feature = "json")]
+impl<T> DeserializeEvent for T
+where
+    T: serde::de::DeserializeOwned,
+{
+    fn deserialize_event(_event_type: &str, data: &[u8]) -> Result<Self, DeserializeError> {
+        serde_json::from_slice(data).map_err(|e| DeserializeError::Invalid(e.to_string()))
+    }
+}
+
+/// Deserializes each [`Event`] from a `Stream<Item = Result<Event, Error<E>>>` into `T`,
+/// silently skipping events whose `event` name doesn't match any of `T`'s variants. Created with
+/// [`TypedEvents::typed_events`].
+pub struct TypedEventStream<S, T> {
+    inner: S,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<S, T> TypedEventStream<S, T> {
+    #[inline]
+    fn projection(self: Pin<&mut Self>) -> Pin<&mut S> {
+        unsafe { self.map_unchecked_mut(|this| &mut this.inner) }
+    }
+}
+
+impl<S, T, E> Stream for TypedEventStream<S, T>
+where
+    S: Stream<Item = Result<Event, Error<E>>>,
+    T: DeserializeEvent,
+{
+    type Item = Result<T, Error<E>>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        loop {
+            match self.as_mut().projection().poll_next(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Ready(Some(Err(e))) => return Poll::Ready(Some(Err(e))),
+                Poll::Ready(Some(Ok(event))) => {
+                    let event_type = event.event.as_deref().unwrap_or("");
+                    match T::deserialize_event(event_type, &event.data) {
+                        Ok(value) => return Poll::Ready(Some(Ok(value))),
+                        Err(DeserializeError::UnknownEventType) => continue,
+                        Err(DeserializeError::Invalid(message)) => {
+                            return Poll::Ready(Some(Err(Error::Parse(ParseError::Deserialize(
+                                message,
+                            )))));
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Adds [`typed_events`](TypedEvents::typed_events) to any `Stream<Item = Result<Event, Error<E>>>`,
+/// such as an [`EventStreamTransformer`](crate::EventStreamTransformer)
+pub trait TypedEvents<E>: Stream<Item = Result<Event, Error<E>>> + Sized {
+    /// Deserialize each [`Event`] into `T`, dispatching on the event's `event` name
+    fn typed_events<T: DeserializeEvent>(self) -> TypedEventStream<Self, T> {
+        TypedEventStream {
+            inner: self,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<S, E> TypedEvents<E> for S where S: Stream<Item = Result<Event, Error<E>>> {}
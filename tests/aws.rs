@@ -0,0 +1,91 @@
+use eventsource_stream::aws::AwsEventStreamDecoder;
+use eventsource_stream::EventStreamTransformer;
+use futures::stream::{self, StreamExt};
+
+fn build_message(event_type: &str, payload: &[u8]) -> Vec<u8> {
+    let mut headers = Vec::new();
+    headers.push(b":event-type".len() as u8);
+    headers.extend_from_slice(b":event-type");
+    headers.push(7u8);
+    headers.extend_from_slice(&(event_type.len() as u16).to_be_bytes());
+    headers.extend_from_slice(event_type.as_bytes());
+
+    let total_length = 12 + headers.len() + payload.len() + 4;
+
+    let mut message = Vec::new();
+    message.extend_from_slice(&(total_length as u32).to_be_bytes());
+    message.extend_from_slice(&(headers.len() as u32).to_be_bytes());
+    let prelude_crc = crc32fast::hash(&message);
+    message.extend_from_slice(&prelude_crc.to_be_bytes());
+    message.extend_from_slice(&headers);
+    message.extend_from_slice(payload);
+    let message_crc = crc32fast::hash(&message);
+    message.extend_from_slice(&message_crc.to_be_bytes());
+
+    message
+}
+
+#[tokio::test]
+async fn decodes_aws_event_stream_frames() {
+    let bytes = build_message("myEvent", b"hello");
+    let mut stream = EventStreamTransformer::with_decoder(
+        stream::iter(vec![Ok::<_, std::io::Error>(bytes)]),
+        AwsEventStreamDecoder::default(),
+    );
+
+    let event = stream.next().await.unwrap().unwrap();
+    assert_eq!(Some("myEvent".to_string()), event.event);
+    assert_eq!(b"hello", &event.data[..]);
+}
+
+#[tokio::test]
+async fn buffers_a_message_split_across_chunks() {
+    let bytes = build_message("myEvent", b"hello");
+    let (first, second) = bytes.split_at(bytes.len() / 2);
+    let mut stream = EventStreamTransformer::with_decoder(
+        stream::iter(vec![
+            Ok::<_, std::io::Error>(first.to_vec()),
+            Ok(second.to_vec()),
+        ]),
+        AwsEventStreamDecoder::default(),
+    );
+
+    let event = stream.next().await.unwrap().unwrap();
+    assert_eq!(Some("myEvent".to_string()), event.event);
+    assert_eq!(b"hello", &event.data[..]);
+}
+
+#[tokio::test]
+async fn reports_truncated_frame_left_over_when_stream_ends() {
+    let bytes = build_message("myEvent", b"hello");
+    let truncated = &bytes[..bytes.len() - 1];
+    let mut stream = EventStreamTransformer::with_decoder(
+        stream::iter(vec![Ok::<_, std::io::Error>(truncated.to_vec())]),
+        AwsEventStreamDecoder::default(),
+    );
+
+    let err = stream.next().await.unwrap().unwrap_err();
+    assert!(matches!(
+        err,
+        eventsource_stream::Error::Parse(eventsource_stream::ParseError::TruncatedFrame)
+    ));
+    assert!(stream.next().await.is_none());
+}
+
+#[tokio::test]
+async fn rejects_corrupted_checksum() {
+    let mut bytes = build_message("myEvent", b"hello");
+    let last = bytes.len() - 1;
+    bytes[last] ^= 0xff;
+
+    let mut stream = EventStreamTransformer::with_decoder(
+        stream::iter(vec![Ok::<_, std::io::Error>(bytes)]),
+        AwsEventStreamDecoder::default(),
+    );
+
+    let err = stream.next().await.unwrap().unwrap_err();
+    assert!(matches!(
+        err,
+        eventsource_stream::Error::Parse(eventsource_stream::ParseError::InvalidChecksum)
+    ));
+}
@@ -28,3 +28,22 @@ line2", String::from_utf8_lossy(&event.data));
     assert_eq!("my-id", String::from_utf8_lossy(&event.id.unwrap()));
     assert_eq!(std::time::Duration::from_millis(42), event.retry.unwrap());
 }
+
+#[tokio::test]
+async fn mixed_line_endings_and_comments() {
+    let url = Url::parse("https://example.com").unwrap();
+    let response = Builder::new()
+        .status(200)
+        .url(url.clone())
+        .body(
+            ": this is a comment and should be ignored\rdata: one\r: another comment\ndata: two\r\n\r\n"
+                .to_string(),
+        )
+        .unwrap();
+    let response = Response::from(response);
+    let mut stream = response.bytes_stream().eventsource();
+
+    let event = stream.next().await.unwrap().unwrap();
+    assert_eq!("one
+two", String::from_utf8_lossy(&event.data));
+}
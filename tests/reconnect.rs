@@ -0,0 +1,91 @@
+use eventsource_stream::reconnect::{
+    ConnectionState, ReconnectError, ReconnectingEventSource, RetryPolicy,
+};
+use futures::stream::{self, StreamExt};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[tokio::test]
+async fn reconnects_with_last_event_id_after_transport_error() {
+    let attempt = Rc::new(RefCell::new(0usize));
+    let seen_last_event_id: Rc<RefCell<Option<Vec<u8>>>> = Rc::new(RefCell::new(None));
+
+    let attempt_for_connect = attempt.clone();
+    let seen_for_connect = seen_last_event_id.clone();
+    let connect = move |last_event_id: Option<&[u8]>| {
+        *seen_for_connect.borrow_mut() = last_event_id.map(|id| id.to_vec());
+        let n = *attempt_for_connect.borrow();
+        *attempt_for_connect.borrow_mut() += 1;
+        async move {
+            if n == 0 {
+                Ok::<_, &'static str>(stream::iter(vec![
+                    Ok(b"id: 1\ndata: first\n\n".to_vec()),
+                    Err("transport dropped"),
+                ]))
+            } else {
+                Ok(stream::iter(vec![Ok(b"data: second\n\n".to_vec())]))
+            }
+        }
+    };
+
+    let mut source = ReconnectingEventSource::new(connect, |_| async {});
+    assert_eq!(ConnectionState::Connecting, source.state());
+
+    let first = source.next().await.unwrap().unwrap();
+    assert_eq!(b"first", &first.data[..]);
+    assert_eq!(ConnectionState::Connected, source.state());
+
+    let second = source.next().await.unwrap().unwrap();
+    assert_eq!(b"second", &second.data[..]);
+    assert_eq!(Some(b"1".to_vec()), *seen_last_event_id.borrow());
+    assert_eq!(ConnectionState::Connected, source.state());
+}
+
+#[tokio::test]
+async fn gives_up_after_max_attempts_when_connect_fails() {
+    let connect = |_last_event_id: Option<&[u8]>| async {
+        Err::<stream::Iter<std::vec::IntoIter<Result<Vec<u8>, &'static str>>>, _>(
+            "connect failed",
+        )
+    };
+
+    let mut source = ReconnectingEventSource::new(connect, |_| async {})
+        .with_retry_policy(RetryPolicy::MaxAttempts(2));
+
+    let err = source.next().await.unwrap().unwrap_err();
+    match err {
+        ReconnectError::GaveUp {
+            last_error,
+            attempts,
+        } => {
+            assert_eq!(Some("connect failed"), last_error);
+            assert_eq!(2, attempts);
+        }
+        _ => panic!("expected ReconnectError::GaveUp"),
+    }
+    assert_eq!(ConnectionState::Closed, source.state());
+    assert!(source.next().await.is_none());
+}
+
+#[tokio::test]
+async fn gives_up_after_max_attempts_when_connection_closes_cleanly() {
+    let connect = |_last_event_id: Option<&[u8]>| async {
+        Ok::<_, &'static str>(stream::iter(Vec::<Result<Vec<u8>, &'static str>>::new()))
+    };
+
+    let mut source = ReconnectingEventSource::new(connect, |_| async {})
+        .with_retry_policy(RetryPolicy::MaxAttempts(1));
+
+    let err = source.next().await.unwrap().unwrap_err();
+    match err {
+        ReconnectError::GaveUp {
+            last_error,
+            attempts,
+        } => {
+            assert_eq!(None, last_error);
+            assert_eq!(1, attempts);
+        }
+        _ => panic!("expected ReconnectError::GaveUp"),
+    }
+    assert_eq!(ConnectionState::Closed, source.state());
+}
@@ -0,0 +1,31 @@
+use eventsource_stream::typed::{DeserializeError, DeserializeEvent, TypedEvents};
+use eventsource_stream::Eventsource;
+use futures::stream::{self, StreamExt};
+
+#[derive(Debug, PartialEq)]
+enum Notification {
+    Greeting(String),
+}
+
+impl DeserializeEvent for Notification {
+    fn deserialize_event(event_type: &str, data: &[u8]) -> Result<Self, DeserializeError> {
+        match event_type {
+            "greeting" => Ok(Notification::Greeting(
+                String::from_utf8(data.to_vec()).map_err(|e| DeserializeError::Invalid(e.to_string()))?,
+            )),
+            _ => Err(DeserializeError::UnknownEventType),
+        }
+    }
+}
+
+#[tokio::test]
+async fn dispatches_on_event_name_and_skips_unknown_ones() {
+    let body = "event: mystery\ndata: ignored\n\nevent: greeting\ndata: hi\n\n";
+    let mut stream = stream::iter(vec![Ok::<_, std::io::Error>(body)])
+        .eventsource()
+        .typed_events::<Notification>();
+
+    let notification = stream.next().await.unwrap().unwrap();
+    assert_eq!(Notification::Greeting("hi".to_string()), notification);
+    assert!(stream.next().await.is_none());
+}